@@ -1,6 +1,11 @@
 use wasm_bindgen::prelude::*;
 
 /// Convert a mono or RGB image (u8 input) to RGBA u8 output using a LUT.
+///
+/// Dispatches to a `simd128`-accelerated mono path when the wasm binary is
+/// built with that target feature, falling back to the scalar loop
+/// otherwise; callers always use this one function name regardless of which
+/// build produced it.
 #[wasm_bindgen]
 pub fn generate_display_image_u8(
     data: &[u8],
@@ -12,14 +17,13 @@ pub fn generate_display_image_u8(
 ) {
     let pixel_count = width * height;
     if channels == 1 {
-        for idx in 0..pixel_count {
-            let tgt_idx = idx * 4;
-            let value = data[idx] as usize;
-            let display = lut.get(value).copied().unwrap_or(0);
-            output[tgt_idx] = display;
-            output[tgt_idx + 1] = display;
-            output[tgt_idx + 2] = display;
-            output[tgt_idx + 3] = 255;
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        {
+            generate_display_image_mono_u8_simd(&data[..pixel_count], lut, output);
+        }
+        #[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+        {
+            generate_display_image_mono_u8_scalar(&data[..pixel_count], lut, output);
         }
     } else if channels == 3 {
         for idx in 0..pixel_count {
@@ -36,6 +40,84 @@ pub fn generate_display_image_u8(
     }
 }
 
+/// Scalar mono u8 -> RGBA gather, used directly on non-wasm32/non-simd128
+/// builds and as the tail handler for the SIMD path below.
+fn generate_display_image_mono_u8_scalar(data: &[u8], lut: &[u8], output: &mut [u8]) {
+    for (idx, &value) in data.iter().enumerate() {
+        let tgt_idx = idx * 4;
+        let display = lut.get(value as usize).copied().unwrap_or(0);
+        output[tgt_idx] = display;
+        output[tgt_idx + 1] = display;
+        output[tgt_idx + 2] = display;
+        output[tgt_idx + 3] = 255;
+    }
+}
+
+/// SIMD mono u8 -> RGBA gather: the LUT lookup itself stays scalar (wasm128
+/// has no data-dependent gather instruction), but each block of 16 looked-up
+/// gray values is expanded into 64 RGBA bytes via `i8x16_shuffle` instead of
+/// a per-pixel scalar write, processing 16 pixels per iteration.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn generate_display_image_mono_u8_simd(data: &[u8], lut: &[u8], output: &mut [u8]) {
+    use core::arch::wasm32::*;
+
+    let pixel_count = data.len();
+    let mut idx = 0;
+    while idx + 16 <= pixel_count {
+        let mut gray = [0u8; 16];
+        for (i, slot) in gray.iter_mut().enumerate() {
+            *slot = lut.get(data[idx + i] as usize).copied().unwrap_or(0);
+        }
+
+        // SAFETY: `gray` is a local 16-byte array, and the four output
+        // slices below are each exactly 16 bytes within `output`'s bounds
+        // (the `output[..]` slicing is bounds-checked and panics before any
+        // store if `output` is shorter than `4 * pixel_count`).
+        unsafe {
+            let gray_v = v128_load(gray.as_ptr() as *const v128);
+            let alpha = u8x16_splat(255);
+
+            // Each shuffle packs 4 source gray lanes into 16 RGBA bytes:
+            // [g, g, g, 255] per pixel. Lane 16 reaches into `alpha`.
+            let group0 =
+                i8x16_shuffle::<0, 0, 0, 16, 1, 1, 1, 16, 2, 2, 2, 16, 3, 3, 3, 16>(gray_v, alpha);
+            let group1 =
+                i8x16_shuffle::<4, 4, 4, 16, 5, 5, 5, 16, 6, 6, 6, 16, 7, 7, 7, 16>(gray_v, alpha);
+            let group2 = i8x16_shuffle::<8, 8, 8, 16, 9, 9, 9, 16, 10, 10, 10, 16, 11, 11, 11, 16>(
+                gray_v, alpha,
+            );
+            let group3 =
+                i8x16_shuffle::<12, 12, 12, 16, 13, 13, 13, 16, 14, 14, 14, 16, 15, 15, 15, 16>(
+                    gray_v, alpha,
+                );
+
+            let out_base = idx * 4;
+            v128_store(
+                output[out_base..out_base + 16].as_mut_ptr() as *mut v128,
+                group0,
+            );
+            v128_store(
+                output[out_base + 16..out_base + 32].as_mut_ptr() as *mut v128,
+                group1,
+            );
+            v128_store(
+                output[out_base + 32..out_base + 48].as_mut_ptr() as *mut v128,
+                group2,
+            );
+            v128_store(
+                output[out_base + 48..out_base + 64].as_mut_ptr() as *mut v128,
+                group3,
+            );
+        }
+
+        idx += 16;
+    }
+
+    if idx < pixel_count {
+        generate_display_image_mono_u8_scalar(&data[idx..], lut, &mut output[idx * 4..]);
+    }
+}
+
 /// Convert a mono or RGB image (u16 input) to RGBA u8 output using a LUT.
 #[wasm_bindgen]
 pub fn generate_display_image_u16(
@@ -108,6 +190,492 @@ pub fn generate_display_image_u32(
     }
 }
 
+/// Number of histogram bins a CLAHE tile LUT covers — the full u16 sample
+/// range, matching the input depth `compute_autostretch_lut_u16` histograms
+/// over rather than an assumed 8-bit display range.
+const CLAHE_BINS: usize = 65536;
+
+/// Build the CDF-based tone-mapping LUT for a single CLAHE tile, clipping
+/// the histogram at `clip_limit` (a fraction of the mean bin count) and
+/// redistributing the clipped excess uniformly across all bins.
+fn build_clahe_tile_lut(histogram: &mut [u32], clip_limit: f32) -> Vec<u8> {
+    let mean = histogram.iter().sum::<u32>() as f32 / histogram.len() as f32;
+    let clip = (mean * clip_limit).max(1.0) as u32;
+
+    let mut excess = 0u32;
+    for bin in histogram.iter_mut() {
+        if *bin > clip {
+            excess += *bin - clip;
+            *bin = clip;
+        }
+    }
+
+    let redistribute = excess / histogram.len() as u32;
+    let remainder = excess % histogram.len() as u32;
+    for (i, bin) in histogram.iter_mut().enumerate() {
+        *bin += redistribute;
+        if (i as u32) < remainder {
+            *bin += 1;
+        }
+    }
+
+    let total: u32 = histogram.iter().sum();
+    let mut lut = vec![0u8; histogram.len()];
+    let mut cdf = 0u32;
+    for (i, &count) in histogram.iter().enumerate() {
+        cdf += count;
+        lut[i] = if total == 0 {
+            0
+        } else {
+            ((cdf as u64 * 255) / total as u64) as u8
+        };
+    }
+    lut
+}
+
+/// Bilinearly interpolate the four tile LUTs surrounding `(x, y)`, clamping
+/// at edges/corners so border pixels fall back to one or two tiles.
+fn interpolate_clahe(
+    tile_luts: &[Vec<u8>],
+    tile_grid: (usize, usize),
+    tile_size: (f32, f32),
+    x: usize,
+    y: usize,
+    value: usize,
+) -> u8 {
+    let (tiles_x, tiles_y) = tile_grid;
+    let (tile_w, tile_h) = tile_size;
+
+    let fx = (x as f32 + 0.5) / tile_w - 0.5;
+    let fy = (y as f32 + 0.5) / tile_h - 0.5;
+
+    let tx0 = fx.floor().clamp(0.0, (tiles_x - 1) as f32) as usize;
+    let ty0 = fy.floor().clamp(0.0, (tiles_y - 1) as f32) as usize;
+    let tx1 = (tx0 + 1).min(tiles_x - 1);
+    let ty1 = (ty0 + 1).min(tiles_y - 1);
+
+    let wx = (fx - tx0 as f32).clamp(0.0, 1.0);
+    let wy = (fy - ty0 as f32).clamp(0.0, 1.0);
+
+    let v00 = tile_luts[ty0 * tiles_x + tx0][value] as f32;
+    let v10 = tile_luts[ty0 * tiles_x + tx1][value] as f32;
+    let v01 = tile_luts[ty1 * tiles_x + tx0][value] as f32;
+    let v11 = tile_luts[ty1 * tiles_x + tx1][value] as f32;
+
+    let top = v00 + (v10 - v00) * wx;
+    let bottom = v01 + (v11 - v01) * wx;
+    (top + (bottom - top) * wy).round().clamp(0.0, 255.0) as u8
+}
+
+/// Tile grid and clipping parameters for `generate_display_image_clahe_u16`.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct ClaheParams {
+    pub tiles_x: usize,
+    pub tiles_y: usize,
+    /// Histogram clip limit, as a fraction of a tile's mean bin count.
+    pub clip_limit: f32,
+}
+
+/// Apply contrast-limited adaptive histogram equalization (CLAHE) to a mono
+/// or RGB u16 image and write the result as RGBA u8.
+///
+/// The image is partitioned into a `tiles_x x tiles_y` grid; each tile gets
+/// its own histogram-derived LUT (clipped at `clip_limit`, a fraction of the
+/// mean bin count, with the excess redistributed uniformly), and each output
+/// pixel bilinearly interpolates the four surrounding tile LUTs. For RGB
+/// input the equalization runs on luminance and the three channels are
+/// scaled proportionally so color is preserved. Like
+/// `generate_display_image_u16`, only `channels == 1` (mono) and
+/// `channels == 3` (RGB) are handled; any other value is a no-op. Samples
+/// are histogrammed over the full u16 range, same as
+/// `compute_autostretch_lut_u16`.
+#[wasm_bindgen]
+pub fn generate_display_image_clahe_u16(
+    data: &[u16],
+    width: usize,
+    height: usize,
+    channels: u8,
+    params: ClaheParams,
+    output: &mut [u8],
+) {
+    let ClaheParams {
+        tiles_x,
+        tiles_y,
+        clip_limit,
+    } = params;
+
+    if width == 0 || height == 0 || tiles_x == 0 || tiles_y == 0 {
+        return;
+    }
+    if channels != 1 && channels != 3 {
+        return;
+    }
+
+    let tile_w = width as f32 / tiles_x as f32;
+    let tile_h = height as f32 / tiles_y as f32;
+
+    let luminance = |idx: usize| -> u16 {
+        if channels == 1 {
+            data[idx]
+        } else {
+            let base = idx * 3;
+            let r = data[base] as u32;
+            let g = data[base + 1] as u32;
+            let b = data[base + 2] as u32;
+            ((r * 299 + g * 587 + b * 114) / 1000) as u16
+        }
+    };
+
+    let mut tile_luts: Vec<Vec<u8>> = Vec::with_capacity(tiles_x * tiles_y);
+    for ty in 0..tiles_y {
+        let y_start = (ty as f32 * tile_h) as usize;
+        let y_end = (((ty + 1) as f32 * tile_h) as usize).min(height);
+        for tx in 0..tiles_x {
+            let x_start = (tx as f32 * tile_w) as usize;
+            let x_end = (((tx + 1) as f32 * tile_w) as usize).min(width);
+
+            let mut histogram = vec![0u32; CLAHE_BINS];
+            for y in y_start..y_end {
+                for x in x_start..x_end {
+                    let idx = y * width + x;
+                    histogram[luminance(idx) as usize] += 1;
+                }
+            }
+            tile_luts.push(build_clahe_tile_lut(&mut histogram, clip_limit));
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let idx = y * width + x;
+            let lum = luminance(idx) as usize;
+            let mapped =
+                interpolate_clahe(&tile_luts, (tiles_x, tiles_y), (tile_w, tile_h), x, y, lum);
+            let tgt_idx = idx * 4;
+
+            if channels == 1 {
+                output[tgt_idx] = mapped;
+                output[tgt_idx + 1] = mapped;
+                output[tgt_idx + 2] = mapped;
+            } else if channels == 3 {
+                let base = idx * 3;
+                let r = data[base] as f32;
+                let g = data[base + 1] as f32;
+                let b = data[base + 2] as f32;
+                let scale = if lum == 0 {
+                    0.0
+                } else {
+                    mapped as f32 / lum as f32
+                };
+                output[tgt_idx] = (r * scale).round().clamp(0.0, 255.0) as u8;
+                output[tgt_idx + 1] = (g * scale).round().clamp(0.0, 255.0) as u8;
+                output[tgt_idx + 2] = (b * scale).round().clamp(0.0, 255.0) as u8;
+            }
+            output[tgt_idx + 3] = 255;
+        }
+    }
+}
+
+/// Selects the tone curve used by [`compute_autostretch_lut_u16`].
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StretchCurve {
+    /// Midtones transfer function, as used by PixInsight/Siril-style
+    /// auto-stretch tools.
+    Mtf = 0,
+    /// `asinh(stretch * x) / asinh(stretch)`, good at preserving faint
+    /// extended structure without blowing out bright cores.
+    Asinh = 1,
+}
+
+/// Tone curve and its tuning knobs for `compute_autostretch_lut_u16`.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct AutostretchParams {
+    pub curve: StretchCurve,
+    /// Black-point offset from the median, in MAD units.
+    pub shadow_clip: f32,
+    /// Target background level in `[0, 1]`; higher lifts midtones more.
+    pub target_bg: f32,
+    /// Stretch factor for `StretchCurve::Asinh`; unused by `Mtf`.
+    pub stretch: f32,
+}
+
+/// Scan a u16 mono/RGB image and derive a display LUT via robust statistics,
+/// filling `out_lut` (expected length 65536) in place.
+///
+/// The black point is estimated as `median + shadow_clip * MAD` (median
+/// absolute deviation), with `hi` taken as the image maximum. The target
+/// background level `target_bg` (in `[0, 1]`) controls how aggressively
+/// midtones are lifted. The resulting LUT is compatible with the existing
+/// `generate_display_image_*` family, so callers can swap a hand-built LUT
+/// for this one without touching the rendering path.
+#[wasm_bindgen]
+pub fn compute_autostretch_lut_u16(
+    data: &[u16],
+    width: usize,
+    height: usize,
+    channels: u8,
+    params: AutostretchParams,
+    out_lut: &mut [u8],
+) {
+    let AutostretchParams {
+        curve,
+        shadow_clip,
+        target_bg,
+        stretch,
+    } = params;
+
+    let pixel_count = width * height * channels.max(1) as usize;
+    if pixel_count == 0 || out_lut.is_empty() {
+        return;
+    }
+
+    let mut histogram = vec![0u32; 65536];
+    for &v in &data[..pixel_count.min(data.len())] {
+        histogram[v as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().map(|&c| c as u64).sum();
+    let median = weighted_percentile(&histogram, total, 0.5);
+
+    let mut abs_dev_histogram = vec![0u32; 65536];
+    for (value, &count) in histogram.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let dev = (value as i32 - median as i32).unsigned_abs() as usize;
+        abs_dev_histogram[dev.min(65535)] += count;
+    }
+    let mad = weighted_percentile(&abs_dev_histogram, total, 0.5);
+
+    let hi = histogram
+        .iter()
+        .rposition(|&c| c > 0)
+        .unwrap_or(65535)
+        .max(1) as f32;
+    let lo = (median as f32 + shadow_clip * mad as f32).clamp(0.0, hi - 1.0);
+
+    let m = target_bg.clamp(0.001, 0.999);
+    let range = out_lut.len() as f32 - 1.0;
+
+    for (i, entry) in out_lut.iter_mut().enumerate() {
+        let x = i as f32 * (65535.0 / range);
+        let normalized = ((x - lo) / (hi - lo)).clamp(0.0, 1.0);
+
+        let mapped = match curve {
+            StretchCurve::Mtf => {
+                if normalized <= 0.0 {
+                    0.0
+                } else {
+                    (m * normalized) / ((2.0 * m - 1.0) * normalized - (m - 1.0))
+                }
+            }
+            StretchCurve::Asinh => {
+                let s = stretch.max(0.0001);
+                (s * normalized).asinh() / s.asinh()
+            }
+        };
+
+        *entry = (mapped.clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+}
+
+/// Find the value whose cumulative histogram count first reaches
+/// `fraction * total`, used to derive median/MAD without sorting the
+/// full pixel array.
+fn weighted_percentile(histogram: &[u32], total: u64, fraction: f64) -> usize {
+    if total == 0 {
+        return 0;
+    }
+    let target = (total as f64 * fraction).ceil() as u64;
+    let mut cumulative = 0u64;
+    for (value, &count) in histogram.iter().enumerate() {
+        cumulative += count as u64;
+        if cumulative >= target {
+            return value;
+        }
+    }
+    histogram.len() - 1
+}
+
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xc0;
+const QOI_OP_RGB: u8 = 0xfe;
+const QOI_OP_RGBA: u8 = 0xff;
+const QOI_END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+#[derive(Clone, Copy, PartialEq)]
+struct QoiPixel {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
+
+impl QoiPixel {
+    fn hash(&self) -> usize {
+        (self.r as usize * 3 + self.g as usize * 5 + self.b as usize * 7 + self.a as usize * 11)
+            % 64
+    }
+}
+
+/// Encode a 4-channel RGBA buffer as a QOI image into `out`, returning the
+/// number of bytes written.
+///
+/// Implements the QOI codec: a 14-byte header (`"qoif"`, big-endian
+/// width/height, channels=4, colorspace byte) followed by a single pass over
+/// pixels that tracks the previous pixel and a 64-entry seen-array indexed
+/// by `(r*3 + g*5 + b*7 + a*11) % 64`, preferring (in order) a run of
+/// identical pixels, an index hit, a small per-channel diff, a luma-biased
+/// diff, then falling back to a literal RGB/RGBA chunk. The stream ends with
+/// the 8-byte QOI end marker. `out` must be large enough to hold the worst
+/// case (`14 + width * height * 5 + 8` bytes).
+#[wasm_bindgen]
+pub fn encode_qoi(rgba: &[u8], width: u32, height: u32, out: &mut [u8]) -> usize {
+    let mut pos = 0usize;
+
+    out[pos..pos + 4].copy_from_slice(b"qoif");
+    pos += 4;
+    out[pos..pos + 4].copy_from_slice(&width.to_be_bytes());
+    pos += 4;
+    out[pos..pos + 4].copy_from_slice(&height.to_be_bytes());
+    pos += 4;
+    out[pos] = 4; // channels
+    pos += 1;
+    out[pos] = 0; // colorspace: sRGB with linear alpha
+    pos += 1;
+
+    let pixel_count = (width as usize) * (height as usize);
+    let mut seen = [QoiPixel {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    }; 64];
+    let mut prev = QoiPixel {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    let mut run = 0u32;
+
+    for idx in 0..pixel_count {
+        let base = idx * 4;
+        let px = QoiPixel {
+            r: rgba[base],
+            g: rgba[base + 1],
+            b: rgba[base + 2],
+            a: rgba[base + 3],
+        };
+
+        if px == prev {
+            run += 1;
+            if run == 62 || idx == pixel_count - 1 {
+                out[pos] = QOI_OP_RUN | (run - 1) as u8;
+                pos += 1;
+                run = 0;
+            }
+            continue;
+        }
+
+        if run > 0 {
+            out[pos] = QOI_OP_RUN | (run - 1) as u8;
+            pos += 1;
+            run = 0;
+        }
+
+        let hash = px.hash();
+        if seen[hash] == px {
+            out[pos] = QOI_OP_INDEX | hash as u8;
+            pos += 1;
+        } else {
+            seen[hash] = px;
+
+            if px.a == prev.a {
+                let dr = px.r.wrapping_sub(prev.r) as i8;
+                let dg = px.g.wrapping_sub(prev.g) as i8;
+                let db = px.b.wrapping_sub(prev.b) as i8;
+
+                if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                    out[pos] = QOI_OP_DIFF
+                        | (((dr + 2) as u8) << 4)
+                        | (((dg + 2) as u8) << 2)
+                        | ((db + 2) as u8);
+                    pos += 1;
+                } else {
+                    let dr_dg = dr.wrapping_sub(dg);
+                    let db_dg = db.wrapping_sub(dg);
+                    if (-32..=31).contains(&dg)
+                        && (-8..=7).contains(&dr_dg)
+                        && (-8..=7).contains(&db_dg)
+                    {
+                        out[pos] = QOI_OP_LUMA | ((dg + 32) as u8);
+                        out[pos + 1] = (((dr_dg + 8) as u8) << 4) | ((db_dg + 8) as u8);
+                        pos += 2;
+                    } else {
+                        out[pos] = QOI_OP_RGB;
+                        out[pos + 1] = px.r;
+                        out[pos + 2] = px.g;
+                        out[pos + 3] = px.b;
+                        pos += 4;
+                    }
+                }
+            } else {
+                out[pos] = QOI_OP_RGBA;
+                out[pos + 1] = px.r;
+                out[pos + 2] = px.g;
+                out[pos + 3] = px.b;
+                out[pos + 4] = px.a;
+                pos += 5;
+            }
+        }
+
+        prev = px;
+    }
+
+    out[pos..pos + 8].copy_from_slice(&QOI_END_MARKER);
+    pos += 8;
+    pos
+}
+
+/// Render a mono u16 image through an RGBA palette (texture-LUT) instead of
+/// a grayscale ramp, for false-color display (e.g. heat/rainbow maps for
+/// SNR or velocity).
+///
+/// `palette_rgba` is indexed directly by pixel value, four bytes per entry,
+/// and is expected to cover the full input range (256 or 65536 entries for
+/// 8-bit and 16-bit source data respectively). If the caller wants a
+/// stretched mapping, pre-stretch the pixel values before building the
+/// palette index, or build the palette to already encode the stretch curve
+/// (e.g. via [`compute_autostretch_lut_u16`]) composed with the desired
+/// color ramp.
+#[wasm_bindgen]
+pub fn generate_display_image_paletted_u16(
+    data: &[u16],
+    width: usize,
+    height: usize,
+    palette_rgba: &[u8],
+    output: &mut [u8],
+) {
+    let pixel_count = width * height;
+    let palette_len = palette_rgba.len() / 4;
+    if palette_len == 0 {
+        return;
+    }
+
+    for (idx, &pixel) in data.iter().enumerate().take(pixel_count) {
+        let value = (pixel as usize).min(palette_len.saturating_sub(1));
+        let src = value * 4;
+        let tgt = idx * 4;
+        output[tgt..tgt + 4].copy_from_slice(&palette_rgba[src..src + 4]);
+    }
+}
+
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
 }
@@ -121,4 +689,298 @@ mod tests {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn clahe_tile_lut_is_monotonic_and_spans_full_output_range() {
+        let mut histogram = vec![0u32; CLAHE_BINS];
+        for (value, count) in histogram.iter_mut().enumerate().take(1000) {
+            *count = (value % 7 + 1) as u32;
+        }
+        let lut = build_clahe_tile_lut(&mut histogram, 2.0);
+        assert_eq!(lut.len(), CLAHE_BINS);
+        assert_eq!(lut[0], 0);
+        assert_eq!(*lut.last().unwrap(), 255);
+        for pair in lut.windows(2) {
+            assert!(pair[1] >= pair[0]);
+        }
+    }
+
+    #[test]
+    fn clahe_single_tile_matches_direct_tile_lut() {
+        // With a single tile there is nothing to interpolate between, so
+        // the whole image should be mapped through that one tile's LUT.
+        let width = 4;
+        let height = 4;
+        let mut data = vec![0u16; width * height];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = (i * 30) as u16;
+        }
+
+        let params = ClaheParams {
+            tiles_x: 1,
+            tiles_y: 1,
+            clip_limit: 2.0,
+        };
+        let mut output = vec![0u8; width * height * 4];
+        generate_display_image_clahe_u16(&data, width, height, 1, params, &mut output);
+
+        let mut histogram = vec![0u32; CLAHE_BINS];
+        for &v in &data {
+            histogram[v as usize] += 1;
+        }
+        let expected = build_clahe_tile_lut(&mut histogram, 2.0);
+
+        for (idx, &v) in data.iter().enumerate() {
+            let tgt = idx * 4;
+            assert_eq!(output[tgt], expected[v as usize]);
+            assert_eq!(output[tgt + 1], expected[v as usize]);
+            assert_eq!(output[tgt + 2], expected[v as usize]);
+            assert_eq!(output[tgt + 3], 255);
+        }
+    }
+
+    #[test]
+    fn clahe_ignores_unsupported_channel_counts() {
+        let data = vec![100u16; 4];
+        let mut output = vec![7u8; 16];
+        let params = ClaheParams {
+            tiles_x: 1,
+            tiles_y: 1,
+            clip_limit: 2.0,
+        };
+        generate_display_image_clahe_u16(&data, 2, 2, 4, params, &mut output);
+        assert!(output.iter().all(|&b| b == 7));
+    }
+
+    fn autostretch_lut(curve: StretchCurve) -> Vec<u8> {
+        let width = 16;
+        let height = 16;
+        let mut data = vec![0u16; width * height];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = ((i * 37) % 4000) as u16;
+        }
+        let mut lut = vec![0u8; 65536];
+        let params = AutostretchParams {
+            curve,
+            shadow_clip: 2.0,
+            target_bg: 0.25,
+            stretch: 2.0,
+        };
+        compute_autostretch_lut_u16(&data, width, height, 1, params, &mut lut);
+        lut
+    }
+
+    #[test]
+    fn autostretch_mtf_lut_is_monotonic_and_reaches_white() {
+        let lut = autostretch_lut(StretchCurve::Mtf);
+        assert_eq!(lut[0], 0);
+        for pair in lut.windows(2) {
+            assert!(pair[1] >= pair[0]);
+        }
+        // Input values at and above the image's max (here well under the
+        // u16 ceiling) should saturate to full white, exercising the
+        // `.clamp(0.0, 1.0)` path before the `* 255.0` cast.
+        assert_eq!(*lut.last().unwrap(), 255);
+    }
+
+    #[test]
+    fn autostretch_asinh_lut_is_monotonic_and_reaches_white() {
+        let lut = autostretch_lut(StretchCurve::Asinh);
+        assert_eq!(lut[0], 0);
+        for pair in lut.windows(2) {
+            assert!(pair[1] >= pair[0]);
+        }
+        assert_eq!(*lut.last().unwrap(), 255);
+    }
+
+    #[test]
+    fn weighted_percentile_finds_median_of_uniform_histogram() {
+        let mut histogram = vec![0u32; 10];
+        for count in histogram.iter_mut() {
+            *count = 1;
+        }
+        let total = 10;
+        assert_eq!(weighted_percentile(&histogram, total, 0.5), 4);
+    }
+
+    const QOI_MASK_2: u8 = 0xc0;
+
+    /// Minimal QOI decoder used only by tests, to round-trip what
+    /// `encode_qoi` produces without depending on an external crate.
+    fn decode_qoi(bytes: &[u8], pixel_count: usize) -> Vec<QoiPixel> {
+        assert_eq!(&bytes[0..4], b"qoif");
+        let mut pos = 14usize;
+        let mut seen = [QoiPixel {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        }; 64];
+        let mut prev = QoiPixel {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        let mut pixels = Vec::with_capacity(pixel_count);
+
+        while pixels.len() < pixel_count {
+            let tag = bytes[pos];
+            if tag == QOI_OP_RGB {
+                prev = QoiPixel {
+                    r: bytes[pos + 1],
+                    g: bytes[pos + 2],
+                    b: bytes[pos + 3],
+                    a: prev.a,
+                };
+                pos += 4;
+            } else if tag == QOI_OP_RGBA {
+                prev = QoiPixel {
+                    r: bytes[pos + 1],
+                    g: bytes[pos + 2],
+                    b: bytes[pos + 3],
+                    a: bytes[pos + 4],
+                };
+                pos += 5;
+            } else if tag & QOI_MASK_2 == QOI_OP_RUN {
+                let run = (tag & 0x3f) + 1;
+                pos += 1;
+                for _ in 0..run {
+                    pixels.push(prev);
+                }
+                continue;
+            } else if tag & QOI_MASK_2 == QOI_OP_INDEX {
+                prev = seen[(tag & 0x3f) as usize];
+                pos += 1;
+            } else if tag & QOI_MASK_2 == QOI_OP_DIFF {
+                let dr = ((tag >> 4) & 0x03) as i8 - 2;
+                let dg = ((tag >> 2) & 0x03) as i8 - 2;
+                let db = (tag & 0x03) as i8 - 2;
+                prev = QoiPixel {
+                    r: prev.r.wrapping_add(dr as u8),
+                    g: prev.g.wrapping_add(dg as u8),
+                    b: prev.b.wrapping_add(db as u8),
+                    a: prev.a,
+                };
+                pos += 1;
+            } else if tag & QOI_MASK_2 == QOI_OP_LUMA {
+                let dg = (tag & 0x3f) as i8 - 32;
+                let byte2 = bytes[pos + 1];
+                let dr_dg = ((byte2 >> 4) & 0x0f) as i8 - 8;
+                let db_dg = (byte2 & 0x0f) as i8 - 8;
+                prev = QoiPixel {
+                    r: prev.r.wrapping_add((dg + dr_dg) as u8),
+                    g: prev.g.wrapping_add(dg as u8),
+                    b: prev.b.wrapping_add((dg + db_dg) as u8),
+                    a: prev.a,
+                };
+                pos += 2;
+            } else {
+                unreachable!("unknown QOI tag byte {tag:#x}");
+            }
+
+            seen[prev.hash()] = prev;
+            pixels.push(prev);
+        }
+
+        pixels
+    }
+
+    #[test]
+    fn qoi_round_trips_mixed_pixel_kinds() {
+        let width = 4u32;
+        let height = 2u32;
+        let pixel_count = (width * height) as usize;
+
+        // A mix designed to exercise RUN (repeats), INDEX (revisits), DIFF
+        // (small deltas), LUMA (green-biased delta) and a literal RGBA.
+        let rgba: Vec<u8> = vec![
+            10, 20, 30, 255, // literal RGB
+            10, 20, 30, 255, // run
+            10, 20, 30, 255, // run
+            11, 21, 31, 255, // diff
+            10, 20, 30, 255, // index (matches first pixel)
+            35, 45, 50, 255, // luma
+            35, 45, 50, 128, // rgba (alpha changes)
+            35, 45, 50, 128, // run of one
+        ];
+        assert_eq!(rgba.len(), pixel_count * 4);
+
+        let mut out = vec![0u8; 14 + pixel_count * 5 + 8];
+        let len = encode_qoi(&rgba, width, height, &mut out);
+        out.truncate(len);
+
+        let decoded = decode_qoi(&out, pixel_count);
+        for (i, px) in decoded.iter().enumerate() {
+            let base = i * 4;
+            assert_eq!(px.r, rgba[base], "pixel {i} r");
+            assert_eq!(px.g, rgba[base + 1], "pixel {i} g");
+            assert_eq!(px.b, rgba[base + 2], "pixel {i} b");
+            assert_eq!(px.a, rgba[base + 3], "pixel {i} a");
+        }
+        assert_eq!(&out[out.len() - 8..], &QOI_END_MARKER);
+    }
+
+    #[test]
+    fn mono_u8_scalar_gathers_through_lut() {
+        let data: Vec<u8> = (0..40).collect();
+        let mut lut = [0u8; 256];
+        for (i, entry) in lut.iter_mut().enumerate() {
+            *entry = (255 - i).min(255) as u8;
+        }
+        let mut output = vec![0u8; data.len() * 4];
+        generate_display_image_mono_u8_scalar(&data, &lut, &mut output);
+
+        for (idx, &value) in data.iter().enumerate() {
+            let tgt = idx * 4;
+            let expected = lut[value as usize];
+            assert_eq!(output[tgt], expected);
+            assert_eq!(output[tgt + 1], expected);
+            assert_eq!(output[tgt + 2], expected);
+            assert_eq!(output[tgt + 3], 255);
+        }
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    #[test]
+    fn mono_u8_simd_matches_scalar_for_odd_pixel_counts() {
+        // 37 is not a multiple of 16, exercising the SIMD path's scalar tail.
+        let data: Vec<u8> = (0..37).map(|i| (i * 7) as u8).collect();
+        let mut lut = [0u8; 256];
+        for (i, entry) in lut.iter_mut().enumerate() {
+            *entry = (i as u32 * 3 % 256) as u8;
+        }
+
+        let mut simd_output = vec![0u8; data.len() * 4];
+        let mut scalar_output = vec![0u8; data.len() * 4];
+        generate_display_image_mono_u8_simd(&data, &lut, &mut simd_output);
+        generate_display_image_mono_u8_scalar(&data, &lut, &mut scalar_output);
+
+        assert_eq!(simd_output, scalar_output);
+    }
+
+    #[test]
+    fn paletted_lookup_clamps_out_of_range_indices() {
+        // 3-entry palette: index 0 is black, 1 is white, 2 is red.
+        let palette: Vec<u8> = vec![0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 255];
+        let data: Vec<u16> = vec![0, 1, 2, 9999];
+        let mut output = vec![0u8; data.len() * 4];
+
+        generate_display_image_paletted_u16(&data, 2, 2, &palette, &mut output);
+
+        assert_eq!(&output[0..4], &[0, 0, 0, 255]);
+        assert_eq!(&output[4..8], &[255, 255, 255, 255]);
+        assert_eq!(&output[8..12], &[255, 0, 0, 255]);
+        // Out-of-range index clamps to the last palette entry rather than
+        // reading past the table.
+        assert_eq!(&output[12..16], &[255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn paletted_lookup_is_noop_for_empty_palette() {
+        let data: Vec<u16> = vec![0, 1];
+        let mut output = vec![9u8; data.len() * 4];
+        generate_display_image_paletted_u16(&data, 2, 1, &[], &mut output);
+        assert!(output.iter().all(|&b| b == 9));
+    }
 }